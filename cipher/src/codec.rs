@@ -0,0 +1,352 @@
+use std::fmt;
+
+const MAGIC: [u8; 2] = *b"GM";
+const VERSION: u8 = 2;
+
+/// Errors that can occur while decoding the `EncryptedMessage` wire
+/// format. Every variant is produced by an explicit check of the input
+/// (its length, magic, version, hex or UTF-8), never by an
+/// out-of-bounds slice or an `unwrap`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageCodecError {
+    TooShort { expected: usize, actual: usize },
+    BadMagic,
+    UnsupportedVersion(u8),
+    BadHex,
+    BadUtf8 { field: &'static str },
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for MessageCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { expected, actual } => write!(
+                f,
+                "message too short: expected at least {expected} bytes, got {actual}"
+            ),
+            Self::BadMagic => write!(f, "bad magic bytes"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported wire format version {version}")
+            }
+            Self::BadHex => write!(f, "invalid hex encoding"),
+            Self::BadUtf8 { field } => write!(f, "{field} is not valid UTF-8"),
+            Self::LengthMismatch {
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "length mismatch for {field}: header said {expected} bytes, but only {actual} remained"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MessageCodecError {}
+
+// `JsError` has a blanket `impl<E: std::error::Error> From<E> for JsError`,
+// so implementing `std::error::Error` above is enough for `?` to convert
+// a `MessageCodecError` at call sites that return `Result<_, JsError>`.
+
+/// The fields carried by an `EncryptedMessage`, decoded from the wire
+/// format without any knowledge of the `EncryptedMessage` wasm type.
+#[derive(Debug)]
+pub(crate) struct DecodedMessage {
+    pub policy: u8,
+    pub timestamp: u64,
+    pub recipient_address: String,
+    pub nonce: Vec<u8>,
+    pub ephemeral_public_key: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Encodes the versioned wire format:
+/// `magic(2) | version(1) | policy(1) | timestamp(8, LE) | recipient_len(2, LE) | recipient_address |
+///  nonce_len(1) | nonce | key_len(1) | ephemeral_public_key | ciphertext_len(4, LE) | ciphertext`.
+///
+/// `timestamp` and `recipient_address` travel alongside the nonce so the
+/// receiver can reconstruct the associated data bound into the AEAD
+/// without any extra input.
+pub(crate) fn encode(
+    policy: u8,
+    timestamp: u64,
+    recipient_address: &str,
+    nonce: &[u8],
+    ephemeral_public_key: &[u8],
+    ciphertext: &[u8],
+) -> Vec<u8> {
+    let recipient_bytes = recipient_address.as_bytes();
+    let mut out = Vec::with_capacity(
+        MAGIC.len()
+            + 1
+            + 1
+            + 8
+            + 2
+            + recipient_bytes.len()
+            + 1
+            + nonce.len()
+            + 1
+            + ephemeral_public_key.len()
+            + 4
+            + ciphertext.len(),
+    );
+    out.extend_from_slice(&MAGIC);
+    out.push(VERSION);
+    out.push(policy);
+    out.extend_from_slice(&timestamp.to_le_bytes());
+    out.extend_from_slice(&(recipient_bytes.len() as u16).to_le_bytes());
+    out.extend_from_slice(recipient_bytes);
+    out.push(nonce.len() as u8);
+    out.extend_from_slice(nonce);
+    out.push(ephemeral_public_key.len() as u8);
+    out.extend_from_slice(ephemeral_public_key);
+    out.extend_from_slice(&(ciphertext.len() as u32).to_le_bytes());
+    out.extend_from_slice(ciphertext);
+    out
+}
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedMessage, MessageCodecError> {
+    const HEADER_LEN: usize = MAGIC.len() + 1 + 1 + 8 + 2; // magic + version + policy + timestamp + recipient_len
+
+    if bytes.len() < HEADER_LEN {
+        return Err(MessageCodecError::TooShort {
+            expected: HEADER_LEN,
+            actual: bytes.len(),
+        });
+    }
+
+    if bytes[0..MAGIC.len()] != MAGIC {
+        return Err(MessageCodecError::BadMagic);
+    }
+
+    let version = bytes[2];
+    if version != VERSION {
+        return Err(MessageCodecError::UnsupportedVersion(version));
+    }
+
+    let policy = bytes[3];
+    let timestamp = u64::from_le_bytes(
+        bytes[4..12]
+            .try_into()
+            .expect("slice is exactly 8 bytes"),
+    );
+
+    let mut offset = 12;
+    let recipient_len =
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().expect("slice is 2 bytes")) as usize;
+    offset += 2;
+    if bytes.len() < offset + recipient_len {
+        return Err(MessageCodecError::LengthMismatch {
+            field: "recipient_address",
+            expected: recipient_len,
+            actual: bytes.len() - offset,
+        });
+    }
+    let recipient_address = String::from_utf8(bytes[offset..offset + recipient_len].to_vec())
+        .map_err(|_| MessageCodecError::BadUtf8 {
+            field: "recipient_address",
+        })?;
+    offset += recipient_len;
+
+    if bytes.len() < offset + 1 {
+        return Err(MessageCodecError::TooShort {
+            expected: offset + 1,
+            actual: bytes.len(),
+        });
+    }
+    let nonce_len = bytes[offset] as usize;
+    offset += 1;
+    if bytes.len() < offset + nonce_len {
+        return Err(MessageCodecError::LengthMismatch {
+            field: "nonce",
+            expected: nonce_len,
+            actual: bytes.len() - offset,
+        });
+    }
+    let nonce = bytes[offset..offset + nonce_len].to_vec();
+    offset += nonce_len;
+
+    if bytes.len() < offset + 1 {
+        return Err(MessageCodecError::TooShort {
+            expected: offset + 1,
+            actual: bytes.len(),
+        });
+    }
+    let key_len = bytes[offset] as usize;
+    offset += 1;
+    if bytes.len() < offset + key_len {
+        return Err(MessageCodecError::LengthMismatch {
+            field: "ephemeral_public_key",
+            expected: key_len,
+            actual: bytes.len() - offset,
+        });
+    }
+    let ephemeral_public_key = bytes[offset..offset + key_len].to_vec();
+    offset += key_len;
+
+    if bytes.len() < offset + 4 {
+        return Err(MessageCodecError::TooShort {
+            expected: offset + 4,
+            actual: bytes.len(),
+        });
+    }
+    let ct_len = u32::from_le_bytes(
+        bytes[offset..offset + 4]
+            .try_into()
+            .expect("slice is exactly 4 bytes"),
+    ) as usize;
+    offset += 4;
+
+    if bytes.len() < offset + ct_len {
+        return Err(MessageCodecError::LengthMismatch {
+            field: "ciphertext",
+            expected: ct_len,
+            actual: bytes.len() - offset,
+        });
+    }
+    let ciphertext = bytes[offset..offset + ct_len].to_vec();
+
+    Ok(DecodedMessage {
+        policy,
+        timestamp,
+        recipient_address,
+        nonce,
+        ephemeral_public_key,
+        ciphertext,
+    })
+}
+
+/// Decodes the pre-versioning wire format: a 12-byte nonce, an ephemeral
+/// key sniffed by its SEC1 `0x02`/`0x03` prefix, and whatever bytes
+/// remain as ciphertext — no policy byte, no AAD fields. Kept only so
+/// messages written by older clients can still be read; such messages
+/// predate both the policy field and the recipient/timestamp AAD
+/// binding, so `policy` comes back as `Anonymous` and the AAD fields
+/// come back empty.
+pub(crate) fn decode_legacy(bytes: &[u8]) -> DecodedMessage {
+    let nonce = bytes.get(0..12).unwrap_or(bytes).to_vec();
+
+    let is_sec1_compressed = bytes.len() > 12 && (bytes[12] == 0x02 || bytes[12] == 0x03);
+    let key_size = if is_sec1_compressed { 33 } else { 32 };
+    let key_end = 12 + key_size;
+
+    if bytes.len() < key_end {
+        return DecodedMessage {
+            policy: 0,
+            timestamp: 0,
+            recipient_address: String::new(),
+            nonce,
+            ephemeral_public_key: bytes.get(12..).unwrap_or(&[]).to_vec(),
+            ciphertext: Vec::new(),
+        };
+    }
+
+    let ephemeral_public_key = bytes[12..key_end].to_vec();
+    let ciphertext = bytes.get(key_end..).unwrap_or(&[]).to_vec();
+
+    DecodedMessage {
+        policy: 0,
+        timestamp: 0,
+        recipient_address: String::new(),
+        nonce,
+        ephemeral_public_key,
+        ciphertext,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_encode_decode() {
+        let bytes = encode(0, 1_700_000_000, "kaspa:recipient", &[1u8; 12], &[2u8; 33], &[3u8; 10]);
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.policy, 0);
+        assert_eq!(decoded.timestamp, 1_700_000_000);
+        assert_eq!(decoded.recipient_address, "kaspa:recipient");
+        assert_eq!(decoded.nonce, vec![1u8; 12]);
+        assert_eq!(decoded.ephemeral_public_key, vec![2u8; 33]);
+        assert_eq!(decoded.ciphertext, vec![3u8; 10]);
+    }
+
+    #[test]
+    fn rejects_bad_magic_and_short_input() {
+        assert_eq!(
+            decode(&[0u8; 3]).unwrap_err(),
+            MessageCodecError::TooShort { expected: 14, actual: 3 }
+        );
+        assert_eq!(
+            decode(b"XXabcdefghijkl").unwrap_err(),
+            MessageCodecError::BadMagic
+        );
+    }
+
+    #[test]
+    fn decodes_and_decrypts_a_genuine_legacy_payload() {
+        use chacha20poly1305::{
+            ChaCha20Poly1305, KeyInit,
+            aead::{Aead, AeadCore, OsRng, Payload},
+        };
+        use k256::{PublicKey, SecretKey, ecdh::EphemeralSecret};
+        use kaspa_wrpc_client::prelude::NetworkType;
+
+        let receiver_sk = SecretKey::random(&mut OsRng);
+        let receiver_pk = receiver_sk.public_key();
+
+        let ephemeral_secret = EphemeralSecret::random(&mut OsRng);
+        let ephemeral_public_key = PublicKey::from(&ephemeral_secret);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let shared = ephemeral_secret.diffie_hellman(&receiver_pk);
+
+        let mut okm = [0u8; 32];
+        shared
+            .extract::<sha2::Sha256>(None)
+            .expand(b"", &mut okm)
+            .unwrap();
+        let cipher = ChaCha20Poly1305::new(&okm.into());
+
+        // The baseline wire format has no AAD at all: no policy byte,
+        // no recipient/timestamp binding.
+        let message = "hello from before the AAD binding existed";
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: message.as_bytes(),
+                    aad: &[],
+                },
+            )
+            .unwrap();
+
+        let mut legacy_bytes = Vec::new();
+        legacy_bytes.extend_from_slice(nonce.as_slice());
+        legacy_bytes.extend_from_slice(&ephemeral_public_key.to_sec1_bytes());
+        legacy_bytes.extend_from_slice(&ciphertext);
+
+        let decoded = decode_legacy(&legacy_bytes);
+        assert_eq!(decoded.policy, 0);
+        assert_eq!(decoded.nonce, nonce.as_slice());
+        assert_eq!(
+            decoded.ephemeral_public_key,
+            ephemeral_public_key.to_sec1_bytes().to_vec()
+        );
+        assert_eq!(decoded.ciphertext, ciphertext);
+
+        let encrypted = crate::message::EncryptedMessage::from_bytes_legacy(&legacy_bytes);
+        let decrypted = crate::message::decrypt_with_secret_key(
+            encrypted,
+            &receiver_sk.to_bytes(),
+            None,
+            NetworkType::Testnet,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(decrypted.plaintext, message);
+    }
+}
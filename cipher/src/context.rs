@@ -0,0 +1,89 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Contextual metadata bound into a message's AEAD as associated data,
+/// so a ciphertext encrypted for one recipient/conversation can't be
+/// lifted and replayed in another. `recipient_address` and `timestamp`
+/// travel in the `EncryptedMessage` header itself; `sender_address` and
+/// `thread_id` are supplied by the caller on both ends.
+#[wasm_bindgen(inspectable)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageContext {
+    #[wasm_bindgen(skip)]
+    pub recipient_address: String,
+    #[wasm_bindgen(skip)]
+    pub sender_address: Option<String>,
+    #[wasm_bindgen(skip)]
+    pub timestamp: u64,
+    #[wasm_bindgen(skip)]
+    pub thread_id: Option<String>,
+}
+
+#[wasm_bindgen]
+impl MessageContext {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        recipient_address: String,
+        timestamp: u64,
+        sender_address: Option<String>,
+        thread_id: Option<String>,
+    ) -> Self {
+        Self {
+            recipient_address,
+            sender_address,
+            timestamp,
+            thread_id,
+        }
+    }
+}
+
+fn write_field(out: &mut Vec<u8>, field: &[u8]) {
+    out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    out.extend_from_slice(field);
+}
+
+impl MessageContext {
+    /// Canonical, deterministic serialization fed into the AEAD as
+    /// associated data. Every variable-length field is length-prefixed
+    /// so e.g. a `thread_id` that happens to look like part of an
+    /// address can't be confused with it.
+    pub(crate) fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_field(&mut out, self.recipient_address.as_bytes());
+        write_field(
+            &mut out,
+            self.sender_address.as_deref().unwrap_or("").as_bytes(),
+        );
+        out.extend_from_slice(&self.timestamp.to_le_bytes());
+        write_field(&mut out, self.thread_id.as_deref().unwrap_or("").as_bytes());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differs_when_any_field_diverges() {
+        let base = MessageContext::new("kaspa:recipient".into(), 1_000, None, None);
+        let different_recipient = MessageContext::new("kaspa:other".into(), 1_000, None, None);
+        let different_timestamp = MessageContext::new("kaspa:recipient".into(), 1_001, None, None);
+        let different_sender = MessageContext::new(
+            "kaspa:recipient".into(),
+            1_000,
+            Some("kaspa:sender".into()),
+            None,
+        );
+        let different_thread = MessageContext::new(
+            "kaspa:recipient".into(),
+            1_000,
+            None,
+            Some("thread-a".into()),
+        );
+
+        assert_ne!(base.canonical_bytes(), different_recipient.canonical_bytes());
+        assert_ne!(base.canonical_bytes(), different_timestamp.canonical_bytes());
+        assert_ne!(base.canonical_bytes(), different_sender.canonical_bytes());
+        assert_ne!(base.canonical_bytes(), different_thread.canonical_bytes());
+    }
+}
@@ -0,0 +1,133 @@
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, OsRng, Payload, rand_core::RngCore},
+};
+use scrypt::Params as ScryptParams;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsError, prelude::wasm_bindgen};
+
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const SECRET_LEN: usize = 32;
+
+const DEFAULT_LOG_N: u8 = 15; // N = 2^15
+const DEFAULT_R: u32 = 8;
+const DEFAULT_P: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ScryptKdfParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+/// A password-encrypted wallet secret key, ready to be persisted as-is
+/// (e.g. in localStorage or IndexedDB). Every field needed to re-derive
+/// the key and decrypt is carried in the JSON itself.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedKeystore {
+    version: u8,
+    kdf: ScryptKdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, params: &ScryptKdfParams) -> Result<[u8; SECRET_LEN], JsError> {
+    let salt = hex::decode(&params.salt).map_err(|_| JsError::new("Invalid salt encoding"))?;
+    let scrypt_params = ScryptParams::new(params.log_n, params.r, params.p, SECRET_LEN)
+        .map_err(|_| JsError::new("Invalid scrypt parameters"))?;
+
+    let mut key = [0u8; SECRET_LEN];
+    scrypt::scrypt(passphrase.as_bytes(), &salt, &scrypt_params, &mut key)
+        .map_err(|_| JsError::new("Failed to derive key from passphrase"))?;
+    Ok(key)
+}
+
+/// Encrypts a 32-byte wallet secret key under a passphrase, returning a
+/// JSON keystore that carries the scrypt parameters, salt, nonce, and
+/// ChaCha20-Poly1305 ciphertext (with its AEAD tag).
+#[wasm_bindgen]
+pub fn encrypt_secret_key(secret_bytes: &[u8], passphrase: &str) -> Result<String, JsError> {
+    if secret_bytes.len() != SECRET_LEN {
+        return Err(JsError::new("Secret key must be 32 bytes"));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let kdf = ScryptKdfParams {
+        log_n: DEFAULT_LOG_N,
+        r: DEFAULT_R,
+        p: DEFAULT_P,
+        salt: hex::encode(salt),
+    };
+
+    let key = derive_key(passphrase, &kdf)?;
+    let cipher = ChaCha20Poly1305::new(&key.into());
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, secret_bytes)
+        .map_err(|_| JsError::new("Failed to encrypt secret key"))?;
+
+    let keystore = EncryptedKeystore {
+        version: 1,
+        kdf,
+        nonce: hex::encode(nonce_bytes),
+        ciphertext: hex::encode(ciphertext),
+    };
+
+    serde_json::to_string(&keystore).map_err(|_| JsError::new("Failed to serialize keystore"))
+}
+
+/// Reverses [`encrypt_secret_key`]: re-derives the key from the
+/// passphrase and the keystore's own scrypt parameters, then verifies
+/// the AEAD tag. A wrong passphrase fails the tag check and returns a
+/// distinct "invalid passphrase" error rather than garbage bytes.
+#[wasm_bindgen]
+pub fn decrypt_secret_key(json: &str, passphrase: &str) -> Result<Vec<u8>, JsError> {
+    let keystore: EncryptedKeystore =
+        serde_json::from_str(json).map_err(|_| JsError::new("Invalid keystore JSON"))?;
+
+    if keystore.version != 1 {
+        return Err(JsError::new("Unsupported keystore version"));
+    }
+
+    let key = derive_key(passphrase, &keystore.kdf)?;
+    let cipher = ChaCha20Poly1305::new(&key.into());
+
+    let nonce_bytes =
+        hex::decode(&keystore.nonce).map_err(|_| JsError::new("Invalid nonce encoding"))?;
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = hex::decode(&keystore.ciphertext)
+        .map_err(|_| JsError::new("Invalid ciphertext encoding"))?;
+
+    cipher
+        .decrypt(nonce, Payload::from(ciphertext.as_slice()))
+        .map_err(|_| JsError::new("Invalid passphrase"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypts_and_decrypts_with_the_right_passphrase() {
+        let secret = [7u8; SECRET_LEN];
+        let json = encrypt_secret_key(&secret, "correct horse battery staple").unwrap();
+        let decrypted = decrypt_secret_key(&json, "correct horse battery staple").unwrap();
+        assert_eq!(secret.to_vec(), decrypted);
+    }
+
+    #[test]
+    fn rejects_the_wrong_passphrase() {
+        let secret = [7u8; SECRET_LEN];
+        let json = encrypt_secret_key(&secret, "correct horse battery staple").unwrap();
+        assert!(decrypt_secret_key(&json, "wrong passphrase").is_err());
+    }
+}
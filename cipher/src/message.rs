@@ -0,0 +1,710 @@
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, Payload},
+};
+use k256::{
+    PublicKey, SecretKey,
+    ecdh::{EphemeralSecret, SharedSecret, diffie_hellman},
+};
+use kaspa_addresses::{Address, Version};
+use kaspa_wallet_keys::privatekey::PrivateKey as WalletPrivateKey;
+use kaspa_wrpc_client::prelude::NetworkType;
+use secp256k1::{Keypair, Message as SecpMessage, PublicKey as SecpPublicKey, Secp256k1, XOnlyPublicKey};
+use sha2::{Digest, Sha256};
+use std::ops::Deref;
+use wasm_bindgen::{JsError, prelude::wasm_bindgen};
+
+use crate::context::MessageContext;
+
+/// Controls whether, and how, the sender's identity is bound into an
+/// encrypted message.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticationPolicy {
+    /// Today's behavior: pure ephemeral-ECIES. Anyone can encrypt to the
+    /// receiver's address and the receiver has no proof of who sent it.
+    Anonymous = 0,
+    /// The sender's static public key and a Schnorr signature over the
+    /// message are carried inside the plaintext, so the receiver can
+    /// verify the sender's identity after decryption.
+    SenderRevealed = 1,
+    /// The ChaCha key is derived from both the ephemeral DH term and a DH
+    /// term against the sender's static key, so only the holder of the
+    /// sender's secret key can produce ciphertext the receiver can
+    /// decrypt. Forged messages simply fail to decrypt.
+    MutualAuth = 2,
+}
+
+impl AuthenticationPolicy {
+    pub(crate) fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::Anonymous),
+            1 => Some(Self::SenderRevealed),
+            2 => Some(Self::MutualAuth),
+            _ => None,
+        }
+    }
+}
+
+#[wasm_bindgen(inspectable)]
+#[derive(Debug, Clone)]
+pub struct EncryptedMessage {
+    // size is 12 bytes
+    #[wasm_bindgen(skip)]
+    pub nonce: Vec<u8>,
+    // size is 32 or 33 bytes (33 bytes for SEC1 compressed format with 02/03 prefix)
+    #[wasm_bindgen(skip)]
+    pub ephemeral_public_key: Vec<u8>,
+    // size is dynamic
+    #[wasm_bindgen(skip)]
+    pub ciphertext: Vec<u8>,
+    // one of AuthenticationPolicy, stored as a raw tag so the wasm side
+    // doesn't need to round-trip the enum
+    #[wasm_bindgen(skip)]
+    pub policy: u8,
+    // milliseconds since the Unix epoch; part of the AAD bound into the
+    // ciphertext, carried here so the receiver can reconstruct it
+    #[wasm_bindgen(skip)]
+    pub timestamp: u64,
+    // also part of the AAD; the address encryption was performed against
+    #[wasm_bindgen(skip)]
+    pub recipient_address: String,
+    // set only by `from_bytes_legacy`: such messages were encrypted before
+    // the AAD binding existed, so decryption must use empty AAD instead of
+    // reconstructing a `MessageContext` from (empty) header fields.
+    #[wasm_bindgen(skip)]
+    pub is_legacy: bool,
+}
+
+#[wasm_bindgen]
+impl EncryptedMessage {
+    pub fn new(ciphertext: &[u8], nonce: &[u8], ephemeral_public_key: &[u8]) -> Self {
+        Self::new_with_policy(
+            ciphertext,
+            nonce,
+            ephemeral_public_key,
+            AuthenticationPolicy::Anonymous as u8,
+            0,
+            String::new(),
+        )
+    }
+
+    pub fn new_with_policy(
+        ciphertext: &[u8],
+        nonce: &[u8],
+        ephemeral_public_key: &[u8],
+        policy: u8,
+        timestamp: u64,
+        recipient_address: String,
+    ) -> Self {
+        Self {
+            ciphertext: ciphertext.to_vec(),
+            nonce: nonce.to_vec(),
+            ephemeral_public_key: ephemeral_public_key.to_vec(),
+            policy,
+            timestamp,
+            recipient_address,
+            is_legacy: false,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        crate::codec::encode(
+            self.policy,
+            self.timestamp,
+            &self.recipient_address,
+            &self.nonce,
+            &self.ephemeral_public_key,
+            &self.ciphertext,
+        )
+    }
+
+    /// Decodes the current, versioned wire format. Returns an error
+    /// instead of panicking on truncated or malformed input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<EncryptedMessage, JsError> {
+        let decoded = crate::codec::decode(bytes)?;
+        Ok(Self {
+            nonce: decoded.nonce,
+            ephemeral_public_key: decoded.ephemeral_public_key,
+            ciphertext: decoded.ciphertext,
+            policy: decoded.policy,
+            timestamp: decoded.timestamp,
+            recipient_address: decoded.recipient_address,
+            is_legacy: false,
+        })
+    }
+
+    /// Decodes the pre-versioning, prefix-sniffing wire format, for
+    /// messages written by older clients. Such messages predate the AAD
+    /// binding, so `timestamp`/`recipient_address` come back empty and
+    /// `decrypt_core` uses empty AAD for them instead of reconstructing a
+    /// `MessageContext` that was never there to begin with.
+    pub fn from_bytes_legacy(bytes: &[u8]) -> EncryptedMessage {
+        let decoded = crate::codec::decode_legacy(bytes);
+        Self {
+            nonce: decoded.nonce,
+            ephemeral_public_key: decoded.ephemeral_public_key,
+            ciphertext: decoded.ciphertext,
+            policy: decoded.policy,
+            timestamp: decoded.timestamp,
+            recipient_address: decoded.recipient_address,
+            is_legacy: true,
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    #[wasm_bindgen(constructor)]
+    pub fn from_hex(hex: &str) -> Result<EncryptedMessage, JsError> {
+        let bytes = hex::decode(hex).map_err(|_| JsError::from(crate::codec::MessageCodecError::BadHex))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// The result of successfully decrypting a message: the plaintext, plus
+/// (for the authenticated policies) the sender's verified Kaspa address.
+#[wasm_bindgen(inspectable)]
+#[derive(Debug, Clone)]
+pub struct DecryptedMessage {
+    #[wasm_bindgen(skip)]
+    pub plaintext: String,
+    #[wasm_bindgen(skip)]
+    pub sender_address: Option<String>,
+}
+
+#[wasm_bindgen]
+impl DecryptedMessage {
+    #[wasm_bindgen(getter)]
+    pub fn plaintext(&self) -> String {
+        self.plaintext.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sender_address(&self) -> Option<String> {
+        self.sender_address.clone()
+    }
+}
+
+fn expand_okm(shared: &SharedSecret) -> Result<[u8; 32], JsError> {
+    let mut okm = [0u8; 32];
+    shared
+        .extract::<Sha256>(None)
+        .expand(b"", &mut okm)
+        .map_err(|_| JsError::new("Failed to expand shared secret"))?;
+    Ok(okm)
+}
+
+/// Derives the MutualAuth key from the ephemeral DH term and the
+/// sender-static DH term, so the key can only be reproduced by someone
+/// holding the sender's secret key.
+fn expand_mutual_okm(
+    ephemeral_shared: &SharedSecret,
+    sender_shared: &SharedSecret,
+) -> Result<[u8; 32], JsError> {
+    let mut ikm = Vec::with_capacity(64);
+    ikm.extend_from_slice(ephemeral_shared.raw_secret_bytes().as_slice());
+    ikm.extend_from_slice(sender_shared.raw_secret_bytes().as_slice());
+
+    let mut okm = [0u8; 32];
+    hkdf::Hkdf::<Sha256>::new(None, &ikm)
+        .expand(b"", &mut okm)
+        .map_err(|_| JsError::new("Failed to expand mutual shared secret"))?;
+    Ok(okm)
+}
+
+fn sign_with_secret_key(sender_sk: &SecretKey, data: &[u8]) -> Result<[u8; 64], JsError> {
+    let secp = Secp256k1::new();
+    let keypair = Keypair::from_seckey_slice(&secp, &sender_sk.to_bytes())
+        .map_err(|_| JsError::new("Invalid sender secret key"))?;
+
+    let digest: [u8; 32] = Sha256::digest(data).into();
+    let message = SecpMessage::from_digest(digest);
+
+    let signature = secp.sign_schnorr(&message, &keypair);
+    Ok(*signature.as_ref())
+}
+
+fn verify_sender_signature(
+    sender_pk: &SecpPublicKey,
+    data: &[u8],
+    signature_bytes: &[u8],
+) -> Result<(), JsError> {
+    let secp = Secp256k1::new();
+    let (xonly, _) = sender_pk.x_only_public_key();
+
+    let digest: [u8; 32] = Sha256::digest(data).into();
+    let message = SecpMessage::from_digest(digest);
+
+    let signature = secp256k1::schnorr::Signature::from_slice(signature_bytes)
+        .map_err(|_| JsError::new("Invalid sender signature"))?;
+
+    secp.verify_schnorr(&signature, &message, &xonly)
+        .map_err(|_| JsError::new("Sender signature verification failed"))
+}
+
+fn sender_address_from_pubkey(
+    network_type: NetworkType,
+    pubkey_sec1: &[u8],
+) -> Result<String, JsError> {
+    let k256_pk = PublicKey::from_sec1_bytes(pubkey_sec1)
+        .map_err(|_| JsError::new("Invalid sender public key"))?;
+    let secp_pk = SecpPublicKey::from_slice(&k256_pk.to_sec1_bytes())
+        .map_err(|_| JsError::new("Invalid sender public key"))?;
+    let (xonly, _) = secp_pk.x_only_public_key();
+
+    let address = Address::new(network_type.into(), Version::PubKey, &xonly.serialize());
+    Ok(address.to_string())
+}
+
+#[wasm_bindgen]
+pub fn encrypt_message(
+    receiver_address_string: &str,
+    message: &str,
+    policy: AuthenticationPolicy,
+    sender_secret_key_bytes: Option<Vec<u8>>,
+    timestamp: u64,
+    sender_address: Option<String>,
+    thread_id: Option<String>,
+) -> Result<EncryptedMessage, JsError> {
+    let receiver_address = Address::try_from(receiver_address_string)?;
+
+    let receiver_xonly_pk = XOnlyPublicKey::from_slice(receiver_address.payload.as_slice())?;
+
+    let receiver_pk_even =
+        SecpPublicKey::from_x_only_public_key(receiver_xonly_pk, secp256k1::Parity::Even);
+
+    let receiver_pk = PublicKey::from_sec1_bytes(&receiver_pk_even.serialize())?;
+
+    let ephemeral_secret = EphemeralSecret::random(&mut OsRng);
+    let ephemeral_public_key = PublicKey::from(&ephemeral_secret);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng); // 96-bits; unique per message
+
+    let ephemeral_shared = ephemeral_secret.diffie_hellman(&receiver_pk);
+
+    let (okm, plaintext) = match policy {
+        AuthenticationPolicy::Anonymous => {
+            (expand_okm(&ephemeral_shared)?, message.as_bytes().to_vec())
+        }
+        AuthenticationPolicy::SenderRevealed => {
+            let sender_sk_bytes = sender_secret_key_bytes
+                .ok_or_else(|| JsError::new("SenderRevealed requires a sender secret key"))?;
+            let sender_sk = SecretKey::from_slice(&sender_sk_bytes)
+                .map_err(|_| JsError::new("Invalid sender secret key"))?;
+            let sender_pk = sender_sk.public_key();
+
+            let mut signed_over = Vec::new();
+            signed_over.extend_from_slice(&ephemeral_public_key.to_sec1_bytes());
+            signed_over.extend_from_slice(nonce.as_slice());
+            signed_over.extend_from_slice(message.as_bytes());
+
+            let signature = sign_with_secret_key(&sender_sk, &signed_over)?;
+
+            let mut plaintext = Vec::with_capacity(33 + 64 + message.len());
+            plaintext.extend_from_slice(&sender_pk.to_sec1_bytes());
+            plaintext.extend_from_slice(&signature);
+            plaintext.extend_from_slice(message.as_bytes());
+
+            (expand_okm(&ephemeral_shared)?, plaintext)
+        }
+        AuthenticationPolicy::MutualAuth => {
+            let sender_sk_bytes = sender_secret_key_bytes
+                .ok_or_else(|| JsError::new("MutualAuth requires a sender secret key"))?;
+            let sender_sk = SecretKey::from_slice(&sender_sk_bytes)
+                .map_err(|_| JsError::new("Invalid sender secret key"))?;
+
+            let sender_shared =
+                diffie_hellman(sender_sk.to_nonzero_scalar(), receiver_pk.as_affine());
+
+            (
+                expand_mutual_okm(&ephemeral_shared, &sender_shared)?,
+                message.as_bytes().to_vec(),
+            )
+        }
+    };
+
+    let cipher = ChaCha20Poly1305::new(&okm.into());
+
+    let context = MessageContext::new(
+        receiver_address_string.to_string(),
+        timestamp,
+        sender_address,
+        thread_id,
+    );
+    let aad = context.canonical_bytes();
+
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext.as_slice(),
+                aad: aad.as_slice(),
+            },
+        )
+        .map_err(|_| JsError::new("Failed to encrypt message"))?;
+
+    Ok(EncryptedMessage::new_with_policy(
+        ciphertext.as_slice(),
+        nonce.as_slice(),
+        ephemeral_public_key.to_sec1_bytes().deref(),
+        policy as u8,
+        timestamp,
+        receiver_address_string.to_string(),
+    ))
+}
+
+/// Shared by all three decrypt entry points: derives the ChaCha key for
+/// `encrypted_message`'s policy, decrypts under the AAD reconstructed
+/// from the header plus the caller-supplied `sender_address`/`thread_id`,
+/// and (for the authenticated policies) verifies/derives the sender's
+/// address.
+fn decrypt_core(
+    encrypted_message: &EncryptedMessage,
+    receiver_sk: &SecretKey,
+    sender_public_key_bytes: Option<Vec<u8>>,
+    network_type: NetworkType,
+    sender_address: Option<String>,
+    thread_id: Option<String>,
+) -> Result<DecryptedMessage, JsError> {
+    let policy = AuthenticationPolicy::from_tag(encrypted_message.policy)
+        .ok_or_else(|| JsError::new("Unknown authentication policy"))?;
+
+    let ephemeral_pk = PublicKey::from_sec1_bytes(&encrypted_message.ephemeral_public_key)
+        .map_err(|_| JsError::new("Invalid ephemeral public key"))?;
+
+    let nonce = Nonce::from_slice(&encrypted_message.nonce);
+
+    let ephemeral_shared =
+        diffie_hellman(receiver_sk.to_nonzero_scalar(), ephemeral_pk.as_affine());
+
+    let okm = match policy {
+        AuthenticationPolicy::Anonymous | AuthenticationPolicy::SenderRevealed => {
+            expand_okm(&ephemeral_shared)?
+        }
+        AuthenticationPolicy::MutualAuth => {
+            let sender_pk_bytes = sender_public_key_bytes
+                .clone()
+                .ok_or_else(|| JsError::new("MutualAuth requires the sender's public key"))?;
+            let sender_pk = PublicKey::from_sec1_bytes(&sender_pk_bytes)
+                .map_err(|_| JsError::new("Invalid sender public key"))?;
+
+            let sender_shared =
+                diffie_hellman(receiver_sk.to_nonzero_scalar(), sender_pk.as_affine());
+
+            expand_mutual_okm(&ephemeral_shared, &sender_shared)?
+        }
+    };
+
+    let cipher = ChaCha20Poly1305::new(&okm.into());
+
+    let aad = if encrypted_message.is_legacy {
+        Vec::new()
+    } else {
+        MessageContext::new(
+            encrypted_message.recipient_address.clone(),
+            encrypted_message.timestamp,
+            sender_address,
+            thread_id,
+        )
+        .canonical_bytes()
+    };
+
+    let plaintext = cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: encrypted_message.ciphertext.as_slice(),
+                aad: aad.as_slice(),
+            },
+        )
+        .map_err(|_| {
+            JsError::new(
+                "Authentication failed - wrong key, tampered ciphertext, or mismatched context",
+            )
+        })?;
+
+    let (message_bytes, resolved_sender_address) = match policy {
+        AuthenticationPolicy::Anonymous => (plaintext, None),
+        AuthenticationPolicy::SenderRevealed => {
+            if plaintext.len() < 33 + 64 {
+                return Err(JsError::new("Malformed sender-revealed plaintext"));
+            }
+            let sender_pk_bytes = &plaintext[0..33];
+            let signature_bytes = &plaintext[33..97];
+            let message_bytes = plaintext[97..].to_vec();
+
+            let sender_pk = SecpPublicKey::from_slice(sender_pk_bytes)
+                .map_err(|_| JsError::new("Invalid embedded sender public key"))?;
+
+            let mut signed_over = Vec::new();
+            signed_over.extend_from_slice(&encrypted_message.ephemeral_public_key);
+            signed_over.extend_from_slice(&encrypted_message.nonce);
+            signed_over.extend_from_slice(&message_bytes);
+
+            verify_sender_signature(&sender_pk, &signed_over, signature_bytes)?;
+
+            let sender_address = sender_address_from_pubkey(network_type, sender_pk_bytes)?;
+            (message_bytes, Some(sender_address))
+        }
+        AuthenticationPolicy::MutualAuth => {
+            let sender_pk_bytes =
+                sender_public_key_bytes.expect("checked above while deriving the key");
+            let sender_address = sender_address_from_pubkey(network_type, &sender_pk_bytes)?;
+            (plaintext, Some(sender_address))
+        }
+    };
+
+    match String::from_utf8(message_bytes) {
+        Ok(s) => Ok(DecryptedMessage {
+            plaintext: s,
+            sender_address: resolved_sender_address,
+        }),
+        Err(_) => Err(JsError::new("Decrypted data is not valid UTF-8")),
+    }
+}
+
+#[wasm_bindgen]
+pub fn decrypt_message(
+    encrypted_message: EncryptedMessage,
+    receiver_wallet_sk: WalletPrivateKey,
+    sender_public_key_bytes: Option<Vec<u8>>,
+    network_type: NetworkType,
+    sender_address: Option<String>,
+    thread_id: Option<String>,
+) -> Result<DecryptedMessage, JsError> {
+    let receiver_sk = SecretKey::from_slice(&receiver_wallet_sk.secret_bytes())
+        .map_err(|_| JsError::new("Invalid receiver private key"))?;
+
+    decrypt_core(
+        &encrypted_message,
+        &receiver_sk,
+        sender_public_key_bytes,
+        network_type,
+        sender_address,
+        thread_id,
+    )
+}
+
+#[wasm_bindgen]
+pub fn decrypt_message_with_bytes(
+    encrypted_message: EncryptedMessage,
+    private_key_bytes: &[u8],
+    sender_public_key_bytes: Option<Vec<u8>>,
+    network_type: NetworkType,
+    sender_address: Option<String>,
+    thread_id: Option<String>,
+) -> Result<DecryptedMessage, JsError> {
+    let wallet_private_key = WalletPrivateKey::try_from_slice(private_key_bytes)
+        .map_err(|e| JsError::new(&format!("Invalid wallet private key: {}", e)))?;
+
+    decrypt_message(
+        encrypted_message,
+        wallet_private_key,
+        sender_public_key_bytes,
+        network_type,
+        sender_address,
+        thread_id,
+    )
+}
+
+#[wasm_bindgen]
+pub fn decrypt_with_secret_key(
+    encrypted_message: EncryptedMessage,
+    secret_key_bytes: &[u8],
+    sender_public_key_bytes: Option<Vec<u8>>,
+    network_type: NetworkType,
+    sender_address: Option<String>,
+    thread_id: Option<String>,
+) -> Result<DecryptedMessage, JsError> {
+    let receiver_sk = SecretKey::from_slice(secret_key_bytes)
+        .map_err(|_| JsError::new("Invalid secret key"))?;
+
+    decrypt_core(
+        &encrypted_message,
+        &receiver_sk,
+        sender_public_key_bytes,
+        network_type,
+        sender_address,
+        thread_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_wallet_keys::prelude::PublicKey as WalletPublicKey;
+
+    fn testnet_address(sk: &SecretKey) -> String {
+        let sec_pk = SecpPublicKey::from_slice(&sk.public_key().to_sec1_bytes()).unwrap();
+        WalletPublicKey::from(sec_pk)
+            .to_address(NetworkType::Testnet)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn sender_revealed_round_trip_reveals_and_verifies_sender() {
+        let receiver_sk = SecretKey::random(&mut OsRng);
+        let receiver_address = testnet_address(&receiver_sk);
+        let sender_sk = SecretKey::random(&mut OsRng);
+        let expected_sender_address = testnet_address(&sender_sk);
+
+        let message = "hello from a known sender";
+        let encrypted = encrypt_message(
+            &receiver_address,
+            message,
+            AuthenticationPolicy::SenderRevealed,
+            Some(sender_sk.to_bytes().to_vec()),
+            1_700_000_000_000,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let decrypted = decrypt_with_secret_key(
+            encrypted,
+            &receiver_sk.to_bytes(),
+            None,
+            NetworkType::Testnet,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(decrypted.plaintext, message);
+        assert_eq!(decrypted.sender_address, Some(expected_sender_address));
+    }
+
+    #[test]
+    fn sender_revealed_rejects_a_forged_signature() {
+        let receiver_sk = SecretKey::random(&mut OsRng);
+        let receiver_pk = receiver_sk.public_key();
+        let receiver_address = testnet_address(&receiver_sk);
+
+        let sender_sk = SecretKey::random(&mut OsRng);
+        let sender_pk = sender_sk.public_key();
+
+        let ephemeral_secret = EphemeralSecret::random(&mut OsRng);
+        let ephemeral_public_key = PublicKey::from(&ephemeral_secret);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ephemeral_shared = ephemeral_secret.diffie_hellman(&receiver_pk);
+
+        // Sign over a message other than the one actually embedded, so the
+        // plaintext's message and the data the signature covers diverge -
+        // simulating a tampered/forged plaintext.
+        let real_message = b"actual message";
+        let mut signed_over = Vec::new();
+        signed_over.extend_from_slice(&ephemeral_public_key.to_sec1_bytes());
+        signed_over.extend_from_slice(nonce.as_slice());
+        signed_over.extend_from_slice(b"a completely different message");
+        let forged_signature = sign_with_secret_key(&sender_sk, &signed_over).unwrap();
+
+        let mut plaintext = Vec::new();
+        plaintext.extend_from_slice(&sender_pk.to_sec1_bytes());
+        plaintext.extend_from_slice(&forged_signature);
+        plaintext.extend_from_slice(real_message);
+
+        let okm = expand_okm(&ephemeral_shared).unwrap();
+        let cipher = ChaCha20Poly1305::new(&okm.into());
+        let context = MessageContext::new(receiver_address.clone(), 1_700_000_000_000, None, None);
+        let aad = context.canonical_bytes();
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: aad.as_slice(),
+                },
+            )
+            .unwrap();
+
+        let encrypted = EncryptedMessage::new_with_policy(
+            &ciphertext,
+            nonce.as_slice(),
+            ephemeral_public_key.to_sec1_bytes().deref(),
+            AuthenticationPolicy::SenderRevealed as u8,
+            1_700_000_000_000,
+            receiver_address,
+        );
+
+        let result = decrypt_with_secret_key(
+            encrypted,
+            &receiver_sk.to_bytes(),
+            None,
+            NetworkType::Testnet,
+            None,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn mutual_auth_round_trip_requires_the_correct_sender_key() {
+        let receiver_sk = SecretKey::random(&mut OsRng);
+        let receiver_address = testnet_address(&receiver_sk);
+        let sender_sk = SecretKey::random(&mut OsRng);
+        let wrong_sender_sk = SecretKey::random(&mut OsRng);
+
+        let message = "only decryptable with the right sender key";
+        let encrypted = encrypt_message(
+            &receiver_address,
+            message,
+            AuthenticationPolicy::MutualAuth,
+            Some(sender_sk.to_bytes().to_vec()),
+            1_700_000_000_000,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let decrypted = decrypt_with_secret_key(
+            encrypted.clone(),
+            &receiver_sk.to_bytes(),
+            Some(sender_sk.public_key().to_sec1_bytes().to_vec()),
+            NetworkType::Testnet,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(decrypted.plaintext, message);
+
+        let result = decrypt_with_secret_key(
+            encrypted,
+            &receiver_sk.to_bytes(),
+            Some(wrong_sender_sk.public_key().to_sec1_bytes().to_vec()),
+            NetworkType::Testnet,
+            None,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn decrypt_fails_when_thread_id_context_does_not_match() {
+        let receiver_sk = SecretKey::random(&mut OsRng);
+        let receiver_address = testnet_address(&receiver_sk);
+
+        let message = "bound to a thread";
+        let encrypted = encrypt_message(
+            &receiver_address,
+            message,
+            AuthenticationPolicy::Anonymous,
+            None,
+            1_700_000_000_000,
+            None,
+            Some("thread-a".to_string()),
+        )
+        .unwrap();
+
+        let result = decrypt_with_secret_key(
+            encrypted,
+            &receiver_sk.to_bytes(),
+            None,
+            NetworkType::Testnet,
+            None,
+            Some("thread-b".to_string()),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use k256::{
+    AffinePoint, FieldBytes, ProjectivePoint, PublicKey, Scalar, SecretKey,
+    elliptic_curve::{ff::PrimeField, group::Group, ops::Reduce, point::AffineCoordinates},
+};
+use kaspa_addresses::Address;
+use secp256k1::{PublicKey as SecpPublicKey, XOnlyPublicKey};
+use sha2::{Digest, Sha256};
+use wasm_bindgen::{JsError, prelude::wasm_bindgen};
+
+const MSG_TAG: &str = "GhostMe/msg";
+const CHALLENGE_TAG: &str = "BIP0340/challenge";
+const NONCE_TAG: &str = "BIP0340/nonce";
+
+/// The secp256k1 field prime `p`, big-endian. BIP340 verification must
+/// fail outright if a signature's `r` is not a canonically-encoded field
+/// element (`r >= p`), rather than silently treating it as *some* point.
+const FIELD_PRIME: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xfc, 0x2f,
+];
+
+/// BIP340's tagged hash: `SHA256(SHA256(tag) || SHA256(tag) || data)`.
+fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+fn scalar_from_bytes(bytes: &[u8; 32]) -> Scalar {
+    <Scalar as Reduce<k256::U256>>::reduce_bytes(FieldBytes::from_slice(bytes))
+}
+
+fn x_coordinate(point: &ProjectivePoint) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&point.to_affine().x());
+    out
+}
+
+/// BIP340 requires the public point to have an even y-coordinate;
+/// negate the point (and its scalar, when known) if it doesn't.
+fn with_even_y(point: ProjectivePoint, scalar: Scalar) -> (ProjectivePoint, Scalar) {
+    if bool::from(point.to_affine().y_is_odd()) {
+        (-point, -scalar)
+    } else {
+        (point, scalar)
+    }
+}
+
+/// Parses the x-only public key out of an address payload exactly as
+/// `debug_address_to_pubkey` does, lifting it to a full point with even
+/// y-parity (BIP340's convention).
+fn x_only_pubkey_to_point(payload: &[u8]) -> Result<AffinePoint, JsError> {
+    let xonly = XOnlyPublicKey::from_slice(payload)
+        .map_err(|e| JsError::new(&format!("Invalid x-only public key: {}", e)))?;
+    let pk_even = SecpPublicKey::from_x_only_public_key(xonly, secp256k1::Parity::Even);
+    let k256_pk = PublicKey::from_sec1_bytes(&pk_even.serialize())
+        .map_err(|e| JsError::new(&format!("Invalid public key encoding: {}", e)))?;
+    Ok(*k256_pk.as_affine())
+}
+
+/// Signs `message` with BIP340 Schnorr over secp256k1, matching Kaspa's
+/// x-only public keys. Returns the 64-byte `(R_x, s)` signature.
+#[wasm_bindgen]
+pub fn sign_message(secret_key_bytes: &[u8], message: &str) -> Result<Vec<u8>, JsError> {
+    let secret_key =
+        SecretKey::from_slice(secret_key_bytes).map_err(|_| JsError::new("Invalid secret key"))?;
+
+    let (public_point, d) = with_even_y(
+        ProjectivePoint::GENERATOR * *secret_key.to_nonzero_scalar(),
+        *secret_key.to_nonzero_scalar(),
+    );
+    let px = x_coordinate(&public_point);
+
+    let msg_hash = tagged_hash(MSG_TAG, message.as_bytes());
+
+    let mut nonce_input = Vec::with_capacity(96);
+    nonce_input.extend_from_slice(&d.to_bytes());
+    nonce_input.extend_from_slice(&px);
+    nonce_input.extend_from_slice(&msg_hash);
+    let k = scalar_from_bytes(&tagged_hash(NONCE_TAG, &nonce_input));
+
+    if bool::from(k.is_zero()) {
+        return Err(JsError::new("Nonce derivation produced a zero scalar"));
+    }
+
+    let (r_point, k) = with_even_y(ProjectivePoint::GENERATOR * k, k);
+    let rx = x_coordinate(&r_point);
+
+    let mut challenge_input = Vec::with_capacity(96);
+    challenge_input.extend_from_slice(&rx);
+    challenge_input.extend_from_slice(&px);
+    challenge_input.extend_from_slice(&msg_hash);
+    let e = scalar_from_bytes(&tagged_hash(CHALLENGE_TAG, &challenge_input));
+
+    let s = k + e * d;
+
+    let mut signature = Vec::with_capacity(64);
+    signature.extend_from_slice(&rx);
+    signature.extend_from_slice(&s.to_bytes());
+    Ok(signature)
+}
+
+/// Verifies a BIP340 Schnorr signature against the x-only public key
+/// carried by `address_string`, proving ownership of that Kaspa address
+/// over `message`.
+#[wasm_bindgen]
+pub fn verify_message(
+    address_string: &str,
+    message: &str,
+    signature: &[u8],
+) -> Result<bool, JsError> {
+    if signature.len() != 64 {
+        return Err(JsError::new("Signature must be 64 bytes"));
+    }
+
+    let rx: [u8; 32] = signature[0..32].try_into().expect("checked length above");
+    if rx >= FIELD_PRIME {
+        // BIP340: fail if r >= p.
+        return Ok(false);
+    }
+
+    let s_bytes: [u8; 32] = signature[32..64].try_into().expect("checked length above");
+    let s = match Option::<Scalar>::from(Scalar::from_repr(FieldBytes::from(s_bytes))) {
+        // BIP340: fail if s >= n. `from_repr` only accepts canonical encodings,
+        // unlike `scalar_from_bytes`'s `Reduce`, which would silently wrap.
+        Some(s) => s,
+        None => return Ok(false),
+    };
+
+    let address = Address::try_from(address_string)?;
+    let public_point = ProjectivePoint::from(x_only_pubkey_to_point(address.payload.as_slice())?);
+    let px = x_coordinate(&public_point);
+
+    let msg_hash = tagged_hash(MSG_TAG, message.as_bytes());
+
+    let mut challenge_input = Vec::with_capacity(96);
+    challenge_input.extend_from_slice(&rx);
+    challenge_input.extend_from_slice(&px);
+    challenge_input.extend_from_slice(&msg_hash);
+    let e = scalar_from_bytes(&tagged_hash(CHALLENGE_TAG, &challenge_input));
+
+    // s*G == R + e*P, so check candidate R = s*G - e*P has the claimed
+    // x-coordinate and the even y-parity BIP340 always expects of R.
+    let candidate_r = ProjectivePoint::GENERATOR * s - public_point * e;
+
+    if bool::from(candidate_r.is_identity()) || bool::from(candidate_r.to_affine().y_is_odd()) {
+        return Ok(false);
+    }
+
+    Ok(x_coordinate(&candidate_r) == rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_wallet_keys::prelude::PublicKey as WalletPublicKey;
+    use kaspa_wrpc_client::prelude::NetworkType;
+
+    #[test]
+    fn signs_and_verifies_round_trip() {
+        let secret_key = SecretKey::random(&mut k256::elliptic_curve::rand_core::OsRng);
+        let sec_pk = SecpPublicKey::from_slice(&secret_key.public_key().to_sec1_bytes()).unwrap();
+        let wallet_pk = WalletPublicKey::from(sec_pk);
+        let address = wallet_pk.to_address(NetworkType::Testnet).unwrap();
+
+        let message = "prove it's me";
+        let signature = sign_message(&secret_key.to_bytes(), message).unwrap();
+
+        assert!(verify_message(&address.to_string(), message, &signature).unwrap());
+        assert!(!verify_message(&address.to_string(), "tampered", &signature).unwrap());
+    }
+}